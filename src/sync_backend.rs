@@ -1,17 +1,166 @@
-use std::{borrow::Cow, io, path::Path};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 use fs_err as fs;
+use image::{imageops::FilterType, GenericImageView, ImageOutputFormat};
 use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::roblox_web_api::{ImageUploadData, RobloxApiClient, RobloxApiError};
 
+/// Roblox rejects (or badly mis-renders) textures larger than this on
+/// either axis, so oversized images are downscaled to fit before upload.
+pub const MAX_DIMENSION: u32 = 1024;
+
 pub trait SyncBackend {
     fn upload(&mut self, data: UploadInfo) -> Result<UploadResponse, Error>;
+
+    /// Removes a previously-uploaded asset, identified by the ID returned
+    /// from `upload`, along with its companion thumbnail asset if one was
+    /// generated (`UploadResponse::thumbnail_id`). Used to garbage-collect
+    /// assets that are no longer referenced by the project. Callers must
+    /// pass `thumbnail_id` whenever the original `UploadResponse` had one,
+    /// or the thumbnail asset is leaked.
+    fn delete(&mut self, id: u64, thumbnail_id: Option<u64>) -> Result<(), Error>;
+}
+
+/// Decodes `data.contents`, downscales it if it exceeds [`MAX_DIMENSION`]
+/// on either axis, and re-encodes it as a canonical PNG. This lets
+/// corrupt or mislabeled images fail locally instead of wasting an
+/// upload round-trip (and a moderation slot) against the Roblox API.
+pub fn validate(data: &UploadInfo) -> Result<Cow<'_, [u8]>, Error> {
+    let image = image::load_from_memory(&data.contents).map_err(|source| Error::Validation {
+        name: data.name.clone(),
+        reason: source.to_string(),
+    })?;
+
+    let (width, height) = image.dimensions();
+
+    let image = if width > MAX_DIMENSION || height > MAX_DIMENSION {
+        log::warn!(
+            "{} is {}x{}, which is larger than the {}x{} Roblox allows. Downscaling...",
+            &data.name,
+            width,
+            height,
+            MAX_DIMENSION,
+            MAX_DIMENSION
+        );
+
+        image.resize(MAX_DIMENSION, MAX_DIMENSION, FilterType::Lanczos3)
+    } else {
+        image
+    };
+
+    let mut encoded = Vec::new();
+    image
+        .write_to(&mut io::Cursor::new(&mut encoded), ImageOutputFormat::Png)
+        .map_err(|source| Error::Validation {
+            name: data.name.clone(),
+            reason: source.to_string(),
+        })?;
+
+    Ok(Cow::Owned(encoded))
+}
+
+/// Decodes `contents` and, if it exceeds `max_edge` on either axis,
+/// resizes it to fit within `max_edge` on its longest edge (preserving
+/// aspect ratio) and re-encodes it as a PNG. Images already within
+/// `max_edge` are returned as the original bytes, without re-encoding.
+/// Used to produce a lightweight companion thumbnail for an asset.
+fn generate_thumbnail(name: &str, contents: &[u8], max_edge: u32) -> Result<Vec<u8>, Error> {
+    let image = image::load_from_memory(contents).map_err(|source| Error::Validation {
+        name: name.to_owned(),
+        reason: source.to_string(),
+    })?;
+
+    let (width, height) = image.dimensions();
+
+    if width <= max_edge && height <= max_edge {
+        return Ok(contents.to_vec());
+    }
+
+    let thumbnail = image.resize(max_edge, max_edge, FilterType::Lanczos3);
+
+    let mut encoded = Vec::new();
+    thumbnail
+        .write_to(&mut io::Cursor::new(&mut encoded), ImageOutputFormat::Png)
+        .map_err(|source| Error::Validation {
+            name: name.to_owned(),
+            reason: source.to_string(),
+        })?;
+
+    Ok(encoded)
+}
+
+#[cfg(test)]
+mod generate_thumbnail_tests {
+    use super::*;
+
+    fn encode_png(width: u32, height: u32) -> Vec<u8> {
+        let image = image::RgbImage::new(width, height);
+        let mut encoded = Vec::new();
+        image::DynamicImage::ImageRgb8(image)
+            .write_to(&mut io::Cursor::new(&mut encoded), ImageOutputFormat::Png)
+            .unwrap();
+
+        encoded
+    }
+
+    #[test]
+    fn downscales_to_fit_within_max_edge() {
+        let source = encode_png(2000, 1000);
+
+        let thumbnail = generate_thumbnail("test", &source, 200).unwrap();
+        let decoded = image::load_from_memory(&thumbnail).unwrap();
+
+        assert!(decoded.width() <= 200);
+        assert!(decoded.height() <= 200);
+    }
+
+    #[test]
+    fn preserves_aspect_ratio() {
+        let source = encode_png(2000, 1000);
+
+        let thumbnail = generate_thumbnail("test", &source, 200).unwrap();
+        let decoded = image::load_from_memory(&thumbnail).unwrap();
+
+        // The source is 2:1, so the thumbnail's longest edge (width)
+        // should hit the cap while height is scaled proportionally.
+        assert_eq!(decoded.width(), 200);
+        assert_eq!(decoded.height(), 100);
+    }
+
+    #[test]
+    fn leaves_images_already_within_max_edge_at_their_own_size() {
+        let source = encode_png(50, 50);
+
+        let thumbnail = generate_thumbnail("test", &source, 200).unwrap();
+        let decoded = image::load_from_memory(&thumbnail).unwrap();
+
+        assert_eq!(decoded.width(), 50);
+        assert_eq!(decoded.height(), 50);
+    }
+
+    #[test]
+    fn rejects_undecodable_input() {
+        let result = generate_thumbnail("test", b"not an image", 200);
+        assert!(matches!(result, Err(Error::Validation { .. })));
+    }
 }
 
 pub struct UploadResponse {
     pub id: u64,
+
+    /// The ID of the downscaled companion thumbnail uploaded alongside
+    /// `id`, if the backend was configured to generate one.
+    pub thumbnail_id: Option<u64>,
 }
 
 pub struct UploadInfo {
@@ -20,16 +169,211 @@ pub struct UploadInfo {
     pub hash: String,
 }
 
+/// Tracks Tarmac's remaining Roblox upload budget for the current window.
+/// `RobloxApiClient` doesn't currently surface Roblox's rate limit
+/// headers, so the budget is inferred from a conservative default and
+/// corrected downward whenever we actually get rate limited.
+struct RateLimit {
+    remaining: i32,
+    reset_at: Instant,
+    limit: i32,
+    window: Duration,
+}
+
+impl RateLimit {
+    const DEFAULT_LIMIT: i32 = 60;
+    const DEFAULT_WINDOW: Duration = Duration::from_secs(60);
+
+    fn new() -> Self {
+        Self {
+            remaining: Self::DEFAULT_LIMIT,
+            reset_at: Instant::now() + Self::DEFAULT_WINDOW,
+            limit: Self::DEFAULT_LIMIT,
+            window: Self::DEFAULT_WINDOW,
+        }
+    }
+
+    /// Reserves one unit of upload budget, sleeping until the window
+    /// resets first if the budget is already exhausted.
+    fn consume(&mut self) {
+        let now = Instant::now();
+
+        if now >= self.reset_at {
+            self.remaining = self.limit;
+            self.reset_at = now + self.window;
+        }
+
+        if self.remaining <= 0 {
+            let wait = self.reset_at.saturating_duration_since(now);
+            log::info!(
+                "Roblox upload budget exhausted, waiting {:?} for it to reset",
+                wait
+            );
+            thread::sleep(wait);
+            self.remaining = self.limit;
+            self.reset_at = Instant::now() + self.window;
+        }
+
+        self.remaining -= 1;
+    }
+
+    /// Forces the budget to empty, used when Roblox rate limits us even
+    /// though our own tracking thought we had credit left.
+    fn exhaust(&mut self) {
+        self.remaining = 0;
+    }
+}
+
+#[cfg(test)]
+impl RateLimit {
+    fn with_limit(limit: i32) -> Self {
+        Self {
+            remaining: limit,
+            reset_at: Instant::now() + Self::DEFAULT_WINDOW,
+            limit,
+            window: Self::DEFAULT_WINDOW,
+        }
+    }
+}
+
+#[cfg(test)]
+mod rate_limit_tests {
+    use super::*;
+
+    #[test]
+    fn consume_decrements_remaining_budget() {
+        let mut rate_limit = RateLimit::with_limit(5);
+
+        rate_limit.consume();
+
+        assert_eq!(rate_limit.remaining, 4);
+    }
+
+    #[test]
+    fn exhaust_zeroes_remaining_without_touching_reset_at() {
+        let mut rate_limit = RateLimit::with_limit(5);
+        let reset_at = rate_limit.reset_at;
+
+        rate_limit.exhaust();
+
+        assert_eq!(rate_limit.remaining, 0);
+        assert_eq!(rate_limit.reset_at, reset_at);
+    }
+
+    #[test]
+    fn consume_refills_once_the_window_has_elapsed() {
+        let mut rate_limit = RateLimit::with_limit(5);
+        rate_limit.remaining = 0;
+        rate_limit.reset_at = Instant::now() - Duration::from_secs(1);
+
+        rate_limit.consume();
+
+        assert_eq!(rate_limit.remaining, rate_limit.limit - 1);
+    }
+}
+
 pub struct RobloxSyncBackend<'a> {
     api_client: &'a mut RobloxApiClient,
     upload_to_group_id: Option<u64>,
+    rate_limit: RateLimit,
+    max_retries: u32,
+    thumbnail_max_edge: Option<u32>,
+    validate: bool,
 }
 
 impl<'a> RobloxSyncBackend<'a> {
+    const BASE_BACKOFF: Duration = Duration::from_secs(2);
+    const MAX_BACKOFF: Duration = Duration::from_secs(64);
+
     pub fn new(api_client: &'a mut RobloxApiClient, upload_to_group_id: Option<u64>) -> Self {
         Self {
             api_client,
             upload_to_group_id,
+            rate_limit: RateLimit::new(),
+            max_retries: 5,
+            thumbnail_max_edge: None,
+            validate: true,
+        }
+    }
+
+    /// Additionally generates and uploads a downscaled companion
+    /// thumbnail for every asset, fit within `max_edge` on its longest
+    /// edge.
+    pub fn with_thumbnails(mut self, max_edge: u32) -> Self {
+        self.thumbnail_max_edge = Some(max_edge);
+        self
+    }
+
+    /// Controls whether images are decoded, dimension-checked, and
+    /// re-encoded to a canonical PNG before upload. Enabled by default;
+    /// disable it if you've already validated images upstream and want
+    /// to skip the extra decode/encode pass.
+    pub fn with_validation(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    /// Uploads `contents` under `name`, retrying with exponential
+    /// backoff if Roblox rate limits us, and returns the resulting
+    /// asset ID.
+    fn upload_bytes(&mut self, name: &str, contents: &[u8]) -> Result<u64, Error> {
+        let mut backoff = Self::BASE_BACKOFF;
+        let mut retries = 0;
+
+        loop {
+            // Only gate on our own inferred budget before the first attempt of
+            // this call. Once Roblox has actually rate limited us, recovery is
+            // driven purely by the exponential backoff below; re-running the
+            // proactive `consume` wait here as well would stack a full-window
+            // sleep on top of every backoff step.
+            if retries == 0 {
+                self.rate_limit.consume();
+            }
+
+            let result = self
+                .api_client
+                .upload_image_with_moderation_retry(ImageUploadData {
+                    image_data: Cow::Borrowed(contents),
+                    name,
+                    description: "Uploaded by Tarmac.",
+                    group_id: self.upload_to_group_id,
+                });
+
+            match result {
+                Ok(response) => {
+                    log::info!("Uploaded {} to ID {}", name, response.backing_asset_id);
+
+                    return Ok(response.backing_asset_id);
+                }
+
+                Err(RobloxApiError::ResponseError {
+                    status: StatusCode::TOO_MANY_REQUESTS,
+                    ..
+                }) if retries < self.max_retries => {
+                    retries += 1;
+                    self.rate_limit.exhaust();
+
+                    log::warn!(
+                        "Rate limited uploading {}, retrying in {:?} (attempt {}/{})",
+                        name,
+                        backoff,
+                        retries,
+                        self.max_retries
+                    );
+
+                    thread::sleep(backoff);
+                    backoff = (backoff * 2).min(Self::MAX_BACKOFF);
+                }
+
+                Err(RobloxApiError::ResponseError {
+                    status: StatusCode::TOO_MANY_REQUESTS,
+                    ..
+                }) => {
+                    return Err(Error::RateLimited);
+                }
+
+                Err(err) => return Err(err.into()),
+            }
         }
     }
 }
@@ -38,35 +382,36 @@ impl<'a> SyncBackend for RobloxSyncBackend<'a> {
     fn upload(&mut self, data: UploadInfo) -> Result<UploadResponse, Error> {
         log::info!("Uploading {} to Roblox", &data.name);
 
-        let result = self
-            .api_client
-            .upload_image_with_moderation_retry(ImageUploadData {
-                image_data: Cow::Owned(data.contents),
-                name: &data.name,
-                description: "Uploaded by Tarmac.",
-                group_id: self.upload_to_group_id,
-            });
-
-        match result {
-            Ok(response) => {
-                log::info!(
-                    "Uploaded {} to ID {}",
-                    &data.name,
-                    response.backing_asset_id
-                );
-
-                Ok(UploadResponse {
-                    id: response.backing_asset_id,
-                })
+        let contents = if self.validate {
+            validate(&data)?
+        } else {
+            Cow::Borrowed(data.contents.as_slice())
+        };
+        let id = self.upload_bytes(&data.name, &contents)?;
+
+        let thumbnail_id = match self.thumbnail_max_edge {
+            Some(max_edge) => {
+                let thumbnail_name = format!("{} (thumbnail)", &data.name);
+                let thumbnail = generate_thumbnail(&data.name, &contents, max_edge)?;
+
+                Some(self.upload_bytes(&thumbnail_name, &thumbnail)?)
             }
+            None => None,
+        };
+
+        Ok(UploadResponse { id, thumbnail_id })
+    }
 
-            Err(RobloxApiError::ResponseError {
-                status: StatusCode::TOO_MANY_REQUESTS,
-                ..
-            }) => Err(Error::RateLimited),
+    fn delete(&mut self, id: u64, thumbnail_id: Option<u64>) -> Result<(), Error> {
+        log::info!("Deleting asset {} from Roblox", id);
+        self.api_client.delete_asset(id)?;
 
-            Err(err) => Err(err.into()),
+        if let Some(thumbnail_id) = thumbnail_id {
+            log::info!("Deleting thumbnail asset {} from Roblox", thumbnail_id);
+            self.api_client.delete_asset(thumbnail_id)?;
         }
+
+        Ok(())
     }
 }
 
@@ -76,15 +421,47 @@ impl SyncBackend for NoneSyncBackend {
     fn upload(&mut self, _data: UploadInfo) -> Result<UploadResponse, Error> {
         Err(Error::NoneBackend)
     }
+
+    fn delete(&mut self, _id: u64, _thumbnail_id: Option<u64>) -> Result<(), Error> {
+        Err(Error::NoneBackend)
+    }
 }
 
 pub struct DebugSyncBackend {
     last_id: u64,
+    thumbnail_max_edge: Option<u32>,
+    validate: bool,
 }
 
 impl DebugSyncBackend {
     pub fn new() -> Self {
-        Self { last_id: 0 }
+        Self {
+            last_id: 0,
+            thumbnail_max_edge: None,
+            validate: false,
+        }
+    }
+
+    /// Additionally generates and copies a downscaled companion
+    /// thumbnail for every asset, fit within `max_edge` on its longest
+    /// edge.
+    pub fn with_thumbnails(mut self, max_edge: u32) -> Self {
+        self.thumbnail_max_edge = Some(max_edge);
+        self
+    }
+
+    /// Controls whether images are decoded, dimension-checked, and
+    /// re-encoded to a canonical PNG before being copied to
+    /// `.tarmac-debug/`. Disabled by default, to keep debug output as
+    /// close to the source bytes as possible.
+    pub fn with_validation(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    fn next_id(&mut self) -> u64 {
+        self.last_id += 1;
+        self.last_id
     }
 }
 
@@ -92,16 +469,396 @@ impl SyncBackend for DebugSyncBackend {
     fn upload(&mut self, data: UploadInfo) -> Result<UploadResponse, Error> {
         log::info!("Copying {} to local folder", &data.name);
 
-        self.last_id += 1;
-        let id = self.last_id;
+        let contents = if self.validate {
+            validate(&data)?
+        } else {
+            Cow::Borrowed(data.contents.as_slice())
+        };
 
         let path = Path::new(".tarmac-debug");
         fs::create_dir_all(path)?;
 
-        let file_path = path.join(id.to_string());
-        fs::write(&file_path, &data.contents)?;
+        let id = self.next_id();
+        fs::write(path.join(id.to_string()), contents.as_ref())?;
+
+        let thumbnail_id = match self.thumbnail_max_edge {
+            Some(max_edge) => {
+                let thumbnail = generate_thumbnail(&data.name, &contents, max_edge)?;
+                let thumbnail_id = self.next_id();
+                fs::write(path.join(thumbnail_id.to_string()), &thumbnail)?;
+
+                Some(thumbnail_id)
+            }
+            None => None,
+        };
+
+        Ok(UploadResponse { id, thumbnail_id })
+    }
+
+    fn delete(&mut self, id: u64, thumbnail_id: Option<u64>) -> Result<(), Error> {
+        let path = Path::new(".tarmac-debug");
+
+        log::info!("Deleting asset {} from local folder", id);
+        fs::remove_file(path.join(id.to_string()))?;
 
-        Ok(UploadResponse { id })
+        if let Some(thumbnail_id) = thumbnail_id {
+            log::info!(
+                "Deleting thumbnail asset {} from local folder",
+                thumbnail_id
+            );
+            fs::remove_file(path.join(thumbnail_id.to_string()))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Controls how `HttpSyncBackend` sends `UploadInfo::contents` to the
+/// target URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpUploadMode {
+    /// Send the image bytes as the request body, unmodified.
+    Raw,
+
+    /// Send the image bytes as a `multipart/form-data` file part named
+    /// `file`.
+    Multipart,
+}
+
+/// Looks up the asset ID in a JSON response body at the given RFC 6901
+/// JSON pointer (e.g. `/id` or `/data/id`).
+fn extract_id(body: &serde_json::Value, id_pointer: &str) -> Option<u64> {
+    body.pointer(id_pointer).and_then(|value| value.as_u64())
+}
+
+#[cfg(test)]
+mod extract_id_tests {
+    use super::*;
+
+    #[test]
+    fn finds_id_at_top_level_pointer() {
+        let body = serde_json::json!({ "id": 123 });
+        assert_eq!(extract_id(&body, "/id"), Some(123));
+    }
+
+    #[test]
+    fn finds_id_at_nested_pointer() {
+        let body = serde_json::json!({ "data": { "id": 456 } });
+        assert_eq!(extract_id(&body, "/data/id"), Some(456));
+    }
+
+    #[test]
+    fn returns_none_for_missing_pointer() {
+        let body = serde_json::json!({ "id": 123 });
+        assert_eq!(extract_id(&body, "/data/id"), None);
+    }
+
+    #[test]
+    fn returns_none_for_non_integer_value() {
+        let body = serde_json::json!({ "id": "not-a-number" });
+        assert_eq!(extract_id(&body, "/id"), None);
+    }
+}
+
+/// Uploads assets to an arbitrary HTTP endpoint that accepts image bytes
+/// and responds with JSON containing the new asset's ID. Useful for
+/// self-hosted or third-party image stores that aren't Roblox.
+pub struct HttpSyncBackend {
+    client: reqwest::blocking::Client,
+    url: String,
+    mode: HttpUploadMode,
+    auth_header: Option<String>,
+    client_id: Option<String>,
+    id_pointer: String,
+    validate: bool,
+}
+
+impl HttpSyncBackend {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::blocking::Client::new(),
+            url: url.into(),
+            mode: HttpUploadMode::Raw,
+            auth_header: None,
+            client_id: None,
+            id_pointer: "/id".to_owned(),
+            validate: false,
+        }
+    }
+
+    pub fn with_mode(mut self, mode: HttpUploadMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn with_auth_header(mut self, auth_header: impl Into<String>) -> Self {
+        self.auth_header = Some(auth_header.into());
+        self
+    }
+
+    pub fn with_client_id(mut self, client_id: impl Into<String>) -> Self {
+        self.client_id = Some(client_id.into());
+        self
+    }
+
+    /// Sets the JSON pointer (RFC 6901, e.g. `/data/id`) used to find the
+    /// asset ID in the response body. Defaults to `/id`.
+    pub fn with_id_pointer(mut self, id_pointer: impl Into<String>) -> Self {
+        self.id_pointer = id_pointer.into();
+        self
+    }
+
+    /// Controls whether images are decoded, dimension-checked, and
+    /// re-encoded to a canonical PNG before upload. Disabled by default,
+    /// since arbitrary HTTP endpoints may not share Roblox's texture
+    /// size limits.
+    pub fn with_validation(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+}
+
+impl SyncBackend for HttpSyncBackend {
+    fn upload(&mut self, data: UploadInfo) -> Result<UploadResponse, Error> {
+        log::info!("Uploading {} to {}", &data.name, &self.url);
+
+        let contents = if self.validate {
+            validate(&data)?.into_owned()
+        } else {
+            data.contents.clone()
+        };
+
+        let mut request = match self.mode {
+            HttpUploadMode::Raw => self.client.post(&self.url).body(contents),
+            HttpUploadMode::Multipart => {
+                let part = reqwest::blocking::multipart::Part::bytes(contents)
+                    .file_name(data.name.clone());
+                let form = reqwest::blocking::multipart::Form::new().part("file", part);
+
+                self.client.post(&self.url).multipart(form)
+            }
+        };
+
+        if let Some(auth_header) = &self.auth_header {
+            request = request.header(reqwest::header::AUTHORIZATION, auth_header.as_str());
+        }
+
+        if let Some(client_id) = &self.client_id {
+            request = request.header("Client-ID", client_id.as_str());
+        }
+
+        let response = request
+            .send()
+            .map_err(|source| Error::Http(source.to_string()))?;
+        let status = response.status();
+
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+
+            return Err(Error::Http(format!(
+                "request to {} failed with status {}: {}",
+                &self.url, status, body
+            )));
+        }
+
+        let body: serde_json::Value = response
+            .json()
+            .map_err(|source| Error::Http(source.to_string()))?;
+
+        let id = extract_id(&body, &self.id_pointer).ok_or_else(|| {
+            Error::Http(format!(
+                "response from {} had no integer id at pointer '{}'",
+                &self.url, &self.id_pointer
+            ))
+        })?;
+
+        Ok(UploadResponse {
+            id,
+            thumbnail_id: None,
+        })
+    }
+
+    fn delete(&mut self, _id: u64, _thumbnail_id: Option<u64>) -> Result<(), Error> {
+        Err(Error::Unsupported(format!(
+            "The HTTP backend for {} cannot delete assets",
+            &self.url
+        )))
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Cache {
+    entries: HashMap<String, CacheEntry>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct CacheEntry {
+    id: u64,
+    thumbnail_id: Option<u64>,
+    last_accessed_unix: u64,
+}
+
+impl CacheEntry {
+    /// Returns whether this entry was last accessed recently enough to
+    /// still be within `ttl` of `now`.
+    fn is_fresh(&self, now: u64, ttl: Duration) -> bool {
+        now.saturating_sub(self.last_accessed_unix) < ttl.as_secs()
+    }
+}
+
+#[cfg(test)]
+mod cache_entry_tests {
+    use super::*;
+
+    fn entry(last_accessed_unix: u64) -> CacheEntry {
+        CacheEntry {
+            id: 1,
+            thumbnail_id: None,
+            last_accessed_unix,
+        }
+    }
+
+    #[test]
+    fn is_fresh_within_ttl() {
+        let entry = entry(1_000);
+        assert!(entry.is_fresh(1_500, Duration::from_secs(600)));
+    }
+
+    #[test]
+    fn is_fresh_right_at_ttl_boundary_is_stale() {
+        let entry = entry(1_000);
+        assert!(!entry.is_fresh(1_600, Duration::from_secs(600)));
+    }
+
+    #[test]
+    fn is_fresh_well_past_ttl_is_stale() {
+        let entry = entry(1_000);
+        assert!(!entry.is_fresh(10_000, Duration::from_secs(600)));
+    }
+
+    #[test]
+    fn sliding_access_keeps_entry_fresh() {
+        let mut entry = entry(1_000);
+        let ttl = Duration::from_secs(600);
+
+        assert!(entry.is_fresh(1_500, ttl));
+        entry.last_accessed_unix = 1_500;
+
+        // Without the refresh above this would be stale (2_000 - 1_000 > 600).
+        assert!(entry.is_fresh(2_000, ttl));
+    }
+}
+
+/// Wraps another `SyncBackend` with a content-addressed cache, keyed by
+/// `UploadInfo::hash`, persisted on disk at `cache_path`. Uploads whose
+/// hash is already cached are short-circuited, and every cache hit pushes
+/// that entry's expiry back. An entry that hasn't been accessed within
+/// `ttl` is evicted the next time its hash is uploaded; stale entries
+/// whose hash is never uploaded again are not removed from the cache file.
+pub struct CachedSyncBackend<'a> {
+    inner: &'a mut dyn SyncBackend,
+    cache_path: PathBuf,
+    cache: Cache,
+    ttl: Duration,
+}
+
+impl<'a> CachedSyncBackend<'a> {
+    const DEFAULT_TTL: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+
+    pub fn new(inner: &'a mut dyn SyncBackend, cache_path: impl Into<PathBuf>) -> Self {
+        Self::with_ttl(inner, cache_path, Self::DEFAULT_TTL)
+    }
+
+    pub fn with_ttl(
+        inner: &'a mut dyn SyncBackend,
+        cache_path: impl Into<PathBuf>,
+        ttl: Duration,
+    ) -> Self {
+        let cache_path = cache_path.into();
+        let cache = Self::load(&cache_path).unwrap_or_default();
+
+        Self {
+            inner,
+            cache_path,
+            cache,
+            ttl,
+        }
+    }
+
+    fn load(path: &Path) -> Option<Cache> {
+        let contents = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    fn save(&self) -> Result<(), Error> {
+        let contents =
+            serde_json::to_string_pretty(&self.cache).expect("Cache should always be valid JSON");
+        fs::write(&self.cache_path, contents)?;
+
+        Ok(())
+    }
+
+    fn now_unix() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+    }
+}
+
+impl<'a> SyncBackend for CachedSyncBackend<'a> {
+    fn upload(&mut self, data: UploadInfo) -> Result<UploadResponse, Error> {
+        let now = Self::now_unix();
+
+        if let Some(entry) = self.cache.entries.get_mut(&data.hash) {
+            if entry.is_fresh(now, self.ttl) {
+                log::info!("Using cached upload for {} ({})", &data.name, &data.hash);
+
+                entry.last_accessed_unix = now;
+                let id = entry.id;
+                let thumbnail_id = entry.thumbnail_id;
+                self.save()?;
+
+                return Ok(UploadResponse { id, thumbnail_id });
+            }
+
+            log::info!(
+                "Cached upload for {} is older than the TTL, evicting",
+                &data.hash
+            );
+            self.cache.entries.remove(&data.hash);
+        }
+
+        let hash = data.hash.clone();
+        let response = self.inner.upload(data)?;
+
+        self.cache.entries.insert(
+            hash,
+            CacheEntry {
+                id: response.id,
+                thumbnail_id: response.thumbnail_id,
+                last_accessed_unix: now,
+            },
+        );
+        self.save()?;
+
+        Ok(response)
+    }
+
+    fn delete(&mut self, id: u64, thumbnail_id: Option<u64>) -> Result<(), Error> {
+        self.cache.entries.retain(|_, entry| entry.id != id);
+
+        for entry in self.cache.entries.values_mut() {
+            if entry.thumbnail_id == Some(id)
+                || (thumbnail_id.is_some() && entry.thumbnail_id == thumbnail_id)
+            {
+                entry.thumbnail_id = None;
+            }
+        }
+
+        self.save()?;
+
+        self.inner.delete(id, thumbnail_id)
     }
 }
 
@@ -113,6 +870,15 @@ pub enum Error {
     #[error("Tarmac was rate-limited trying to upload assets. Try again in a little bit.")]
     RateLimited,
 
+    #[error("Image '{name}' failed validation: {reason}")]
+    Validation { name: String, reason: String },
+
+    #[error("{0}")]
+    Http(String),
+
+    #[error("{0}")]
+    Unsupported(String),
+
     #[error(transparent)]
     Io {
         #[from]